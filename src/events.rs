@@ -0,0 +1,90 @@
+//! Push notifications for epoch rotations
+//!
+//! Clients can subscribe to `/events` over either a WebSocket or a
+//! Server-Sent-Events stream and learn the moment the epoch rotates,
+//! rather than polling `/info` and racing the puncture in the rotation
+//! loop. Both transports are fed from a single `tokio::sync::broadcast`
+//! channel so every subscriber sees the same sequence of events.
+
+use std::convert::Infallible;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures::Stream;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tracing::debug;
+
+/// Sender half of the epoch-rotation broadcast channel
+pub type EpochSender = broadcast::Sender<EpochEvent>;
+
+/// Notification published whenever the epoch advances or the key rotates
+///
+/// Field names mirror `InfoResponse` so clients can reuse the same parser.
+#[derive(Clone, Serialize, Debug)]
+pub struct EpochEvent {
+    /// Newly active randomness epoch
+    #[serde(rename = "currentEpoch")]
+    pub current_epoch: u8,
+    /// Timestamp of the next epoch rotation, RFC 3339
+    #[serde(rename = "nextEpochTime")]
+    pub next_epoch_time: Option<String>,
+    /// ServerPublicKey for the active key, base64-encoded
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+}
+
+/// Subscribe to epoch rotations over WebSocket or Server-Sent Events
+///
+/// A request carrying the WebSocket upgrade headers is upgraded; any
+/// other request receives an SSE stream.
+pub async fn events(
+    ws: Option<WebSocketUpgrade>,
+    State(sender): State<EpochSender>,
+) -> Response {
+    let receiver = sender.subscribe();
+    match ws {
+        Some(ws) => ws.on_upgrade(move |socket| forward_ws(socket, receiver)),
+        None => sse(receiver).into_response(),
+    }
+}
+
+/// Relay broadcast events to a WebSocket client as JSON text frames
+async fn forward_ws(mut socket: WebSocket, mut receiver: broadcast::Receiver<EpochEvent>) {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => {
+                let text = match serde_json::to_string(&event) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        debug!("failed to serialize epoch event: {e}");
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    // Client hung up.
+                    break;
+                }
+            }
+            // Skip missed events on lag; close on a shutdown of the channel.
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Build an SSE response that relays broadcast events as JSON data frames
+fn sse(
+    receiver: broadcast::Receiver<EpochEvent>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(receiver).filter_map(|result| {
+        // Drop lagged/errored items rather than tearing down the stream.
+        let event = result.ok()?;
+        Event::default().json_data(event).ok().map(Ok)
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
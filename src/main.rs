@@ -1,18 +1,32 @@
 //! STAR Randomness web service
 
-use axum::extract::{Json, State};
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, FromRef, Json, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
 use axum::{routing::get, routing::post, Router};
 use base64::prelude::{Engine as _, BASE64_STANDARD as BASE64};
 use serde::{Deserialize, Serialize};
 use time::format_description::well_known::Rfc3339;
-use tracing::{debug, info, instrument};
+use tracing::{debug, error, info, instrument};
 
 use ppoprf::ppoprf;
+use std::net::SocketAddr;
 use std::sync::{Arc, RwLock};
 
 use clap::Parser;
 
+mod metrics;
+use metrics::Metrics;
+
+mod ratelimit;
+use ratelimit::RateLimiter;
+
+mod events;
+use events::{EpochEvent, EpochSender};
+
+mod persist;
+use persist::PersistedState;
+
 #[cfg(test)]
 mod tests;
 
@@ -29,20 +43,126 @@ struct OPRFServer {
 /// Shareable wrapper around the server state
 type OPRFState = Arc<RwLock<OPRFServer>>;
 
+/// Shared application state handed to every request handler
+///
+/// Groups the locked OPRF state with the lock-free metrics handles so
+/// handlers can extract either through `axum`'s `FromRef` mechanism.
+#[derive(Clone)]
+struct AppState {
+    oprf: OPRFState,
+    metrics: Metrics,
+    /// Optional per-client rate limiter, `None` when disabled
+    limiter: Option<Arc<RateLimiter>>,
+    /// Broadcast channel for epoch-rotation notifications
+    events: EpochSender,
+    /// Parsed service configuration, shared for read-only access
+    config: Arc<Config>,
+}
+
+impl FromRef<AppState> for OPRFState {
+    fn from_ref(state: &AppState) -> Self {
+        state.oprf.clone()
+    }
+}
+
+impl FromRef<AppState> for Metrics {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+impl FromRef<AppState> for Option<Arc<RateLimiter>> {
+    fn from_ref(state: &AppState) -> Self {
+        state.limiter.clone()
+    }
+}
+
+impl FromRef<AppState> for EpochSender {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Config> {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+/// Epoch number valid at `now_unix` under wall-clock anchoring
+///
+/// When rotation is anchored to a reference time the active epoch is a
+/// pure function of the clock, so every replica agrees on the epoch
+/// *number* and not merely the rotation boundary. Clients can compute the
+/// same value from the `epochSeconds`, `epochBaseTime`, `firstEpoch`, and
+/// `lastEpoch` fields of `/info`.
+fn anchored_epoch(config: &Config, now_unix: i64) -> u8 {
+    let base = config
+        .epoch_base_time
+        .expect("anchored_epoch requires a reference time");
+    let period = (config.epoch_seconds as i64).max(1);
+    let range = config.last_epoch as i64 - config.first_epoch as i64 + 1;
+    let index = (now_unix - base).div_euclid(period).rem_euclid(range);
+    config.first_epoch + index as u8
+}
+
 impl OPRFServer {
     /// Initialize a new OPRFServer state with the given configuration
     fn new(config: &Config) -> Result<Self, ppoprf::PPRFError> {
         // ppoprf wants a vector, so generate one from our range.
         let epochs: Vec<u8> =
             (config.first_epoch..=config.last_epoch).collect();
-        let epoch = epochs[0];
-        let server = ppoprf::Server::new(epochs)?;
+        let mut server = ppoprf::Server::new(epochs)?;
+        // When anchored, the active epoch is derived from the wall clock so
+        // all replicas agree on the number even when started at different
+        // times. Puncture the epochs already elapsed in the current cycle so
+        // a freshly started replica matches the privacy posture of one that
+        // has been rotating all along.
+        let epoch = match config.epoch_base_time {
+            Some(_) => {
+                let now = time::OffsetDateTime::now_utc().unix_timestamp();
+                let current = anchored_epoch(config, now);
+                for elapsed in config.first_epoch..current {
+                    server.puncture(elapsed)?;
+                }
+                current
+            }
+            None => config.first_epoch,
+        };
         Ok(OPRFServer {
             server,
             epoch,
             next_epoch_time: None,
         })
     }
+
+    /// Reconstruct the state from a persisted snapshot
+    ///
+    /// The deserialized `ppoprf::Server` carries its own puncture history,
+    /// so resuming does not re-puncture; `next_epoch_time` is left unset
+    /// for the rotation loop to populate on its first iteration.
+    fn restore(
+        persisted: PersistedState,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let server: ppoprf::Server = bincode::deserialize(&persisted.server)?;
+        Ok(OPRFServer {
+            server,
+            epoch: persisted.epoch,
+            next_epoch_time: None,
+        })
+    }
+
+    /// Serialize the current state and write it atomically to `path`
+    fn persist_to(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let server = bincode::serialize(&self.server).map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+        })?;
+        let state = PersistedState {
+            server,
+            epoch: self.epoch,
+        };
+        persist::save(path, &state)
+    }
 }
 
 /// Request format for the randomness endpoint
@@ -87,6 +207,21 @@ struct InfoResponse {
     /// Maximum number of points accepted in a single request
     #[serde(rename = "maxPoints")]
     max_points: usize,
+    /// Length of each epoch in seconds, i.e. the rotation period
+    #[serde(rename = "epochSeconds")]
+    epoch_seconds: u32,
+    /// Unix reference time the rotation schedule is anchored to, when
+    /// aligned. Present only when anchoring is enabled so clients can
+    /// independently derive which epoch is currently valid.
+    #[serde(rename = "epochBaseTime", skip_serializing_if = "Option::is_none")]
+    epoch_base_time: Option<i64>,
+    /// First epoch tag in the rotation cycle, the anchor for deriving the
+    /// current epoch number from the clock.
+    #[serde(rename = "firstEpoch")]
+    first_epoch: u8,
+    /// Last epoch tag in the rotation cycle.
+    #[serde(rename = "lastEpoch")]
+    last_epoch: u8,
 }
 
 /// Response returned to report error conditions
@@ -108,6 +243,8 @@ enum Error {
     BadPoint,
     #[error("Too many points for a single request")]
     TooManyPoints,
+    #[error("Rate limit exceeded")]
+    RateLimited,
     #[error("Invalid epoch {0}`")]
     BadEpoch(u8),
     #[error("Invalid base64 encoding: {0}")]
@@ -116,6 +253,21 @@ enum Error {
     Oprf(#[from] ppoprf::PPRFError),
 }
 
+impl Error {
+    /// Stable, low-cardinality label for metrics reporting
+    fn variant(&self) -> &'static str {
+        match self {
+            Error::LockFailure => "lock_failure",
+            Error::BadPoint => "bad_point",
+            Error::TooManyPoints => "too_many_points",
+            Error::RateLimited => "rate_limited",
+            Error::BadEpoch(_) => "bad_epoch",
+            Error::Base64(_) => "base64",
+            Error::Oprf(_) => "oprf",
+        }
+    }
+}
+
 /// thiserror doesn't generate a `From` impl without
 /// an inner value to wrap. Write one explicitly for
 /// `std::sync::PoisonError<T>` to avoid making the
@@ -128,20 +280,71 @@ impl<T> From<std::sync::PoisonError<T>> for Error {
     }
 }
 
+impl Error {
+    /// HTTP status the variant maps to
+    ///
+    /// Distinguishes client mistakes from payload-size rejections and
+    /// server-side faults so clients and load balancers can tell
+    /// "fix your request" from "retry later / the server is unhealthy".
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::BadPoint | Error::BadEpoch(_) | Error::Base64(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Error::TooManyPoints => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Error::LockFailure | Error::Oprf(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
 impl axum::response::IntoResponse for Error {
     /// Construct an http response from our error type
     fn into_response(self) -> axum::response::Response {
+        let status = self.status_code();
         let body = Json(ErrorResponse {
             message: self.to_string(),
         });
-        (StatusCode::BAD_REQUEST, body).into_response()
+        (status, body).into_response()
     }
 }
 
 /// Process PPOPRF evaluation requests
+///
+/// Thin wrapper around `randomness_inner` that records the error variant
+/// on the failure path; the hot path stays in the inner function.
 async fn randomness(
     State(state): State<OPRFState>,
+    State(metrics): State<Metrics>,
+    State(limiter): State<Option<Arc<RateLimiter>>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(request): Json<RandomnessRequest>,
+) -> Result<Json<RandomnessResponse>, Error> {
+    // Charge the request against the client's token bucket. A large batch
+    // counts proportionally: one token per point.
+    if let Some(limiter) = &limiter {
+        let key = limiter.client_key(&headers, &peer);
+        if !limiter.check(&key, request.points.len() as f64).await {
+            metrics
+                .errors
+                .with_label_values(&[Error::RateLimited.variant()])
+                .inc();
+            return Err(Error::RateLimited);
+        }
+    }
+    randomness_inner(&state, &metrics, request).inspect_err(|e| {
+        metrics.errors.with_label_values(&[e.variant()]).inc();
+    })
+}
+
+/// Evaluate a batch of points, updating evaluation metrics as it goes
+fn randomness_inner(
+    state: &OPRFState,
+    metrics: &Metrics,
+    request: RandomnessRequest,
 ) -> Result<Json<RandomnessResponse>, Error> {
     debug!("recv: {request:?}");
     let state = state.read()?;
@@ -152,10 +355,20 @@ async fn randomness(
     if request.points.len() > MAX_POINTS {
         return Err(Error::TooManyPoints);
     }
+    // Count each request against its resolved epoch so operators can see
+    // evaluation volume per epoch before it is punctured. The metric
+    // handles are atomic, so we update them under the read guard without
+    // taking a second lock.
+    metrics
+        .requests
+        .with_label_values(&[&epoch.to_string()])
+        .inc();
+    metrics.points_per_request.observe(request.points.len() as f64);
     // Don't support returning proofs until we have a more
     // space-efficient batch proof implemented in ppoprf.
     let prove = false;
     let mut points = Vec::with_capacity(request.points.len());
+    let eval_timer = metrics.eval_seconds.start_timer();
     for base64_point in request.points {
         let input = BASE64.decode(base64_point)?;
         // FIXME: Point::from is fallible and needs to return a result.
@@ -166,7 +379,9 @@ async fn randomness(
         let point = ppoprf::Point::from(input.as_slice());
         let evaluation = state.server.eval(&point, epoch, prove)?;
         points.push(BASE64.encode(evaluation.output.as_bytes()));
+        metrics.points_evaluated.inc();
     }
+    eval_timer.observe_duration();
     let response = RandomnessResponse { points, epoch };
     debug!("send: {response:?}");
     Ok(Json(response))
@@ -175,6 +390,18 @@ async fn randomness(
 /// Process PPOPRF epoch and key requests
 async fn info(
     State(state): State<OPRFState>,
+    State(metrics): State<Metrics>,
+    State(config): State<Arc<Config>>,
+) -> Result<Json<InfoResponse>, Error> {
+    info_inner(&state, &config).inspect_err(|e| {
+        metrics.errors.with_label_values(&[e.variant()]).inc();
+    })
+}
+
+/// Build the `InfoResponse` from a snapshot of the OPRF state
+fn info_inner(
+    state: &OPRFState,
+    config: &Config,
 ) -> Result<Json<InfoResponse>, Error> {
     debug!("recv: info request");
     let state = state.read()?;
@@ -185,6 +412,10 @@ async fn info(
         next_epoch_time: state.next_epoch_time.clone(),
         max_points: MAX_POINTS,
         public_key,
+        epoch_seconds: config.epoch_seconds,
+        epoch_base_time: config.epoch_base_time,
+        first_epoch: config.first_epoch,
+        last_epoch: config.last_epoch,
     };
     debug!("send: {response:?}");
     Ok(Json(response))
@@ -192,7 +423,12 @@ async fn info(
 
 /// Advance to the next epoch on a timer
 #[instrument(skip_all)]
-async fn epoch_update_loop(state: OPRFState, config: &Config) {
+async fn epoch_update_loop(
+    state: OPRFState,
+    metrics: Metrics,
+    events: EpochSender,
+    config: &Config,
+) {
     let interval =
         std::time::Duration::from_secs(config.epoch_seconds.into());
     info!("rotating epoch every {} seconds", interval.as_secs());
@@ -201,7 +437,21 @@ async fn epoch_update_loop(state: OPRFState, config: &Config) {
     loop {
         // Pre-calculate the next_epoch_time for the InfoResponse hander.
         let now = time::OffsetDateTime::now_utc();
-        let next_rotation = now + interval;
+        // When anchored to a reference time, rotate on the wall-clock
+        // boundary so independently launched replicas advance in lockstep
+        // and agree on `next_epoch_time`. The first iteration then sleeps
+        // only the remainder of the current period.
+        let (next_rotation, sleep_for) = match config.epoch_base_time {
+            Some(base) => {
+                let period = (interval.as_secs() as i64).max(1);
+                let into_period =
+                    (now.unix_timestamp() - base).rem_euclid(period);
+                let wait = (period - into_period) as u64;
+                let sleep_for = std::time::Duration::from_secs(wait);
+                (now + sleep_for, sleep_for)
+            }
+            None => (now + interval, interval),
+        };
         // Truncate to the nearest second.
         let next_rotation = next_rotation
             .replace_millisecond(0)
@@ -218,10 +468,25 @@ async fn epoch_update_loop(state: OPRFState, config: &Config) {
                 .write()
                 .expect("should be able to update next_epoch_time");
             s.next_epoch_time = Some(timestamp);
+
+            // Announce the now-current epoch (and, after a key rotation,
+            // the new public key) so subscribers can refresh immediately
+            // instead of polling `/info`. A send error just means there
+            // are no subscribers, which is fine.
+            if let Ok(public_key) =
+                s.server.get_public_key().serialize_to_bincode()
+            {
+                let event = EpochEvent {
+                    current_epoch: s.epoch,
+                    next_epoch_time: s.next_epoch_time.clone(),
+                    public_key: BASE64.encode(public_key),
+                };
+                let _ = events.send(event);
+            }
         }
 
         // Wait until the current epoch ends.
-        tokio::time::sleep(interval).await;
+        tokio::time::sleep(sleep_for).await;
 
         // Acquire exclusive access to the oprf state.
         // Panics if this fails, since processing requests with an
@@ -233,10 +498,19 @@ async fn epoch_update_loop(state: OPRFState, config: &Config) {
         s.server
             .puncture(old_epoch)
             .expect("Failed to puncture current epoch");
+        metrics.epoch_punctures.inc();
 
-        // Advance to the next epoch.
-        let new_epoch = old_epoch + 1;
-        if epochs.contains(&new_epoch) {
+        // Advance to the next epoch. When anchored, derive the number from
+        // the wall clock so replicas stay in lockstep; otherwise just step
+        // forward from the current epoch.
+        let new_epoch = match config.epoch_base_time {
+            Some(_) => {
+                let now = time::OffsetDateTime::now_utc().unix_timestamp();
+                anchored_epoch(config, now)
+            }
+            None => old_epoch + 1,
+        };
+        if epochs.contains(&new_epoch) && new_epoch > old_epoch {
             // Server is already initialized for this one.
             s.epoch = new_epoch;
         } else {
@@ -247,32 +521,59 @@ async fn epoch_update_loop(state: OPRFState, config: &Config) {
             *s = OPRFServer::new(config)
                 .expect("Could not initialize new PPOPRF state");
         }
+        metrics.epoch_rotations.inc();
         info!("epoch now {}", s.epoch);
+
+        // Persist the new state so a restart resumes from here rather than
+        // generating a fresh key. Written after puncturing so the on-disk
+        // puncture history can never lag the in-memory state.
+        if let Some(path) = &config.state_file {
+            if let Err(e) = s.persist_to(std::path::Path::new(path)) {
+                error!("failed to persist OPRF state: {e}");
+            }
+        }
     }
 }
 
+/// Render metrics in the Prometheus text exposition format
+async fn metrics_handler(State(metrics): State<Metrics>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        metrics.encode(),
+    )
+}
+
 /// Initialize an axum::Router for our web service
 /// Having this as a separate function makes testing easier.
-fn app(oprf_state: OPRFState) -> Router {
+fn app(state: AppState) -> Router {
     Router::new()
         // Friendly default route to identify the site
         .route("/", get(|| async { "STAR randomness server\n" }))
         // Main endpoints
         .route("/randomness", post(randomness))
         .route("/info", get(info))
+        // Epoch-rotation push notifications (WebSocket or SSE)
+        .route("/events", get(events::events))
+        // Operational metrics in Prometheus text exposition format
+        .route("/metrics", get(metrics_handler))
         // Attach shared state
-        .with_state(oprf_state)
+        .with_state(state)
         // Logging must come after active routes
         .layer(tower_http::trace::TraceLayer::new_for_http())
 }
 
 /// Command line switches
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Config {
     /// Duration of each randomness epoch
     #[arg(long, default_value_t = 5)]
     epoch_seconds: u32,
+    /// Anchor epoch rotation to this Unix reference time (seconds) so all
+    /// replicas rotate on the same wall-clock boundaries. Unset means each
+    /// replica rotates relative to its own start time.
+    #[arg(long)]
+    epoch_base_time: Option<i64>,
     /// First epoch tag to make available
     #[arg(long, default_value_t = 0)]
     first_epoch: u8,
@@ -282,6 +583,115 @@ struct Config {
     /// Host and port to listen for http connections
     #[arg(long, default_value = "127.0.0.1:8080")]
     listen: String,
+    /// Server-side TCP keepalive interval, in seconds. Disabled when unset.
+    #[arg(long)]
+    tcp_keepalive_secs: Option<u64>,
+    /// Enable TCP Fast Open on the listening socket (Linux only).
+    #[arg(long, default_value_t = false)]
+    tcp_fastopen: bool,
+    /// Sustained per-client request rate, in points per second.
+    /// Rate limiting is disabled when unset.
+    #[arg(long)]
+    rate_limit_rps: Option<f64>,
+    /// Maximum per-client burst, in points. Defaults to `rate_limit_rps`.
+    #[arg(long)]
+    rate_limit_burst: Option<f64>,
+    /// Header carrying the client identity, e.g. `X-Forwarded-For`.
+    /// Falls back to the connecting peer address when unset.
+    #[arg(long)]
+    rate_limit_header: Option<String>,
+    /// Redis URL for an approximate cross-instance rate-limit counter.
+    /// This is a cooperative backstop, not a hard global ceiling: each
+    /// instance still enforces its local `burst`, and the shared sum only
+    /// drains a bucket early once the aggregate runs past it. Purely
+    /// in-process buckets are used when unset.
+    #[arg(long)]
+    redis_url: Option<String>,
+    /// Path to persist OPRF key/epoch state across restarts. When set, the
+    /// file is loaded on startup if present and rewritten on each rotation.
+    #[arg(long)]
+    state_file: Option<String>,
+}
+
+/// Future that resolves when the process is asked to terminate
+///
+/// Completes on SIGINT (Ctrl+C) or, on unix, SIGTERM, so orchestrators
+/// can roll the service without dropping in-flight requests.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("shutdown signal received");
+}
+
+/// Build a TCP listener with the configured production tuning applied
+fn build_listener(
+    config: &Config,
+    addr: SocketAddr,
+) -> std::io::Result<std::net::TcpListener> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let socket = Socket::new(
+        Domain::for_address(addr),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )?;
+    socket.set_reuse_address(true)?;
+    if config.tcp_fastopen {
+        enable_tcp_fastopen(&socket)?;
+    }
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+
+    let listener: std::net::TcpListener = socket.into();
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Enable TCP Fast Open on the listening socket
+///
+/// TFO is a kernel-level option with no portable setter, so we set it
+/// directly on Linux and treat it as a no-op elsewhere.
+#[cfg(target_os = "linux")]
+fn enable_tcp_fastopen(socket: &socket2::Socket) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    let queue: libc::c_int = 1024;
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_FASTOPEN,
+            &queue as *const _ as *const libc::c_void,
+            std::mem::size_of_val(&queue) as libc::socklen_t,
+        )
+    };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn enable_tcp_fastopen(_socket: &socket2::Socket) -> std::io::Result<()> {
+    error!("TCP Fast Open requested but not supported on this platform");
+    Ok(())
 }
 
 #[tokio::main]
@@ -296,28 +706,110 @@ async fn main() {
     debug!(?config, "config parsed");
     let addr = config.listen.parse().unwrap();
 
-    // Oblivious function state
+    // Oblivious function state, restored from disk when a state file is
+    // configured and present, otherwise initialized fresh and saved.
     info!("initializing OPRF state...");
-    let server =
-        OPRFServer::new(&config).expect("Could not initialize PPOPRF state");
+    let server = match &config.state_file {
+        Some(path) => {
+            let path = std::path::Path::new(path);
+            match persist::load(path).expect("Could not read state file") {
+                Some(persisted) => {
+                    info!("restoring OPRF state from {}", path.display());
+                    OPRFServer::restore(persisted)
+                        .expect("Could not restore PPOPRF state")
+                }
+                None => {
+                    info!("no state file at {}; initializing fresh", path.display());
+                    let server = OPRFServer::new(&config)
+                        .expect("Could not initialize PPOPRF state");
+                    server
+                        .persist_to(path)
+                        .expect("Could not write initial state file");
+                    server
+                }
+            }
+        }
+        None => {
+            OPRFServer::new(&config).expect("Could not initialize PPOPRF state")
+        }
+    };
     info!("epoch now {}", server.epoch);
     let oprf_state = Arc::new(RwLock::new(server));
 
+    // Metrics registry shared between the handlers and the rotation task
+    let metrics = Metrics::new();
+
+    // Optional per-client rate limiter
+    let limiter = config.rate_limit_rps.map(|rps| {
+        let burst = config.rate_limit_burst.unwrap_or(rps);
+        info!("enabling rate limiting: {rps} rps, burst {burst}");
+        Arc::new(
+            RateLimiter::new(
+                rps,
+                burst,
+                config.rate_limit_header.clone(),
+                config.redis_url.as_deref(),
+            )
+            .expect("Could not initialize rate limiter"),
+        )
+    });
+
+    // Broadcast channel for epoch-rotation notifications. A modest buffer
+    // is plenty: rotations are infrequent and subscribers only need the
+    // latest state, so lagging receivers simply skip the events they missed
+    // and resume with the oldest one still buffered.
+    let (events, _) = tokio::sync::broadcast::channel::<EpochEvent>(16);
+
     // Spawn a background process to advance the epoch
     info!("Spawning background epoch rotation task...");
     let background_state = oprf_state.clone();
+    let background_metrics = metrics.clone();
+    let background_events = events.clone();
+    let background_config = config.clone();
     tokio::spawn(async move {
-        epoch_update_loop(background_state, &config).await
+        epoch_update_loop(
+            background_state,
+            background_metrics,
+            background_events,
+            &background_config,
+        )
+        .await
     });
 
     // Set up routes and middleware
     info!("initializing routes...");
-    let app = app(oprf_state);
+    // Retain a handle to puncture the active epochs on shutdown.
+    let shutdown_state = oprf_state.clone();
+    let app = app(AppState {
+        oprf: oprf_state,
+        metrics,
+        limiter,
+        events,
+        config: Arc::new(config.clone()),
+    });
 
-    // Start the server
+    // Start the server on a tuned listener, honoring shutdown signals.
+    let listener =
+        build_listener(&config, addr).expect("Could not bind listener");
     info!("Listening on {}", &addr);
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .expect("listener should be convertible into a server")
+        .tcp_keepalive(config.tcp_keepalive_secs.map(std::time::Duration::from_secs))
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal());
+    if let Err(e) = server.await {
+        error!("server error: {e}");
+    }
+
+    // Puncture every still-active epoch so no further evaluations can
+    // occur, dropping the private key before exit. Earlier epochs have
+    // already been punctured by the rotation loop.
+    info!("shutting down; puncturing active epochs");
+    if let Ok(mut s) = shutdown_state.write() {
+        for epoch in s.epoch..=config.last_epoch {
+            if let Err(e) = s.server.puncture(epoch) {
+                debug!("puncture of epoch {epoch} on shutdown failed: {e}");
+            }
+        }
+    }
 }
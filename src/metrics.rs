@@ -0,0 +1,137 @@
+//! Prometheus instrumentation for the randomness service
+//!
+//! Kept in a small self-contained module so the hot-path handlers only
+//! touch cheap atomic counters. The individual metric handles are `Clone`
+//! and share their backing storage, so a `Metrics` can be handed to the
+//! request handlers and the epoch rotation task without a second lock.
+
+use std::sync::Arc;
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts,
+    Registry, TextEncoder,
+};
+
+/// Collection of OPRF-specific metrics and their registry
+#[derive(Clone)]
+pub struct Metrics {
+    /// Registry backing the `/metrics` exposition
+    registry: Arc<Registry>,
+    /// Total randomness requests, labelled by resolved epoch
+    pub requests: IntCounterVec,
+    /// Total points evaluated across all requests
+    pub points_evaluated: IntCounter,
+    /// Error responses, labelled by `Error` variant
+    pub errors: IntCounterVec,
+    /// Epoch advances performed by the rotation loop
+    pub epoch_rotations: IntCounter,
+    /// Epochs punctured by the rotation loop
+    pub epoch_punctures: IntCounter,
+    /// Distribution of points per randomness request
+    pub points_per_request: Histogram,
+    /// Distribution of OPRF evaluation latency, in seconds
+    pub eval_seconds: Histogram,
+}
+
+impl Metrics {
+    /// Register all metrics against a fresh registry
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let requests = IntCounterVec::new(
+            Opts::new(
+                "randsrv_requests_total",
+                "Total randomness requests by resolved epoch",
+            ),
+            &["epoch"],
+        )
+        .expect("metric definition should be valid");
+        let points_evaluated = IntCounter::new(
+            "randsrv_points_evaluated_total",
+            "Total points evaluated by the OPRF",
+        )
+        .expect("metric definition should be valid");
+        let errors = IntCounterVec::new(
+            Opts::new(
+                "randsrv_errors_total",
+                "Total error responses by variant",
+            ),
+            &["variant"],
+        )
+        .expect("metric definition should be valid");
+        let epoch_rotations = IntCounter::new(
+            "randsrv_epoch_rotations_total",
+            "Total epoch advances performed by the rotation loop",
+        )
+        .expect("metric definition should be valid");
+        let epoch_punctures = IntCounter::new(
+            "randsrv_epoch_punctures_total",
+            "Total epochs punctured by the rotation loop",
+        )
+        .expect("metric definition should be valid");
+        let points_per_request = Histogram::with_opts(
+            HistogramOpts::new(
+                "randsrv_points_per_request",
+                "Distribution of points per randomness request",
+            )
+            .buckets(vec![1.0, 2.0, 4.0, 8.0, 16.0, 64.0, 256.0, 1024.0]),
+        )
+        .expect("metric definition should be valid");
+        let eval_seconds = Histogram::with_opts(HistogramOpts::new(
+            "randsrv_eval_seconds",
+            "Distribution of OPRF evaluation latency in seconds",
+        ))
+        .expect("metric definition should be valid");
+
+        registry
+            .register(Box::new(requests.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(points_evaluated.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(errors.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(epoch_rotations.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(epoch_punctures.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(points_per_request.clone()))
+            .expect("metric should not be registered twice");
+        registry
+            .register(Box::new(eval_seconds.clone()))
+            .expect("metric should not be registered twice");
+
+        Metrics {
+            registry: Arc::new(registry),
+            requests,
+            points_evaluated,
+            errors,
+            epoch_rotations,
+            epoch_punctures,
+            points_per_request,
+            eval_seconds,
+        }
+    }
+
+    /// Render the current metrics in the Prometheus text exposition format
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        let families = self.registry.gather();
+        encoder
+            .encode(&families, &mut buffer)
+            .expect("text encoding of metrics should not fail");
+        String::from_utf8(buffer)
+            .expect("prometheus text format is valid utf-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,74 @@
+//! On-disk persistence of OPRF key and epoch state
+//!
+//! Persisting the `ppoprf::Server` across restarts lets a process resume
+//! with the same key and puncture history instead of constructing a fresh
+//! key that would invalidate every outstanding client point. State is
+//! written atomically (temp file + rename) so a crash mid-write cannot
+//! corrupt an existing file.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Serializable snapshot of the OPRF service state
+///
+/// The serialized `ppoprf::Server` carries its own puncture history, so the
+/// already-punctured epochs need not be tracked separately to resume.
+#[derive(Serialize, Deserialize)]
+pub struct PersistedState {
+    /// bincode serialization of the `ppoprf::Server`, including its key
+    pub server: Vec<u8>,
+    /// Currently-valid randomness epoch
+    pub epoch: u8,
+}
+
+/// Read persisted state, returning `None` when the file does not yet exist
+pub fn load(path: &Path) -> io::Result<Option<PersistedState>> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            let state = bincode::deserialize(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Ok(Some(state))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write state atomically so a crash mid-write leaves the old file intact
+pub fn save(path: &Path, state: &PersistedState) -> io::Result<()> {
+    let bytes = bincode::serialize(state)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    // Stage in a sibling temp file, then rename over the target. Rename is
+    // atomic on the same filesystem, so readers never see a partial write.
+    // The file holds the private key, so restrict it to the owner.
+    let tmp = path.with_extension("tmp");
+    write_private(&tmp, &bytes)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Write `bytes` to `path`, restricting the file to owner read/write (0600)
+///
+/// The state file contains the OPRF private key, so it must not be readable
+/// by other users. On non-unix platforms the permissions are left at the
+/// filesystem default.
+#[cfg(unix)]
+fn write_private(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(bytes)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    fs::write(path, bytes)
+}
@@ -0,0 +1,253 @@
+//! Per-client rate limiting for the randomness endpoint
+//!
+//! Each client identity gets a token bucket. A large batch counts
+//! proportionally against the limit: a request for `n` points needs `n`
+//! tokens. When a Redis backend is configured the local buckets act as a
+//! fast, approximate first line of defence and are periodically
+//! reconciled against a shared counter so bursts are bounded locally
+//! while multi-instance deployments gain visibility into the aggregate
+//! rate. The reconciliation is intentionally approximate: it folds each
+//! instance's locally-consumed tokens into a windowed cross-instance sum
+//! and drains the local bucket once that sum runs past `burst`. It does
+//! not enforce a hard global ceiling — under steady load the effective
+//! per-instance limit stays close to the local `burst` — so the shared
+//! counter is best thought of as a cooperative backstop rather than a
+//! distributed token bucket.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use dashmap::DashMap;
+use tracing::warn;
+
+/// Number of local requests between authoritative Redis reconciliations
+const RECONCILE_EVERY_REQUESTS: u32 = 16;
+/// Maximum wall-clock gap between reconciliations
+const RECONCILE_EVERY: Duration = Duration::from_millis(500);
+/// Number of requests between idle-bucket sweeps
+///
+/// Bounds the map so a stream of distinct client keys (e.g. a spoofed
+/// `X-Forwarded-For` or many peer IPs) cannot grow memory without bound.
+const SWEEP_EVERY_REQUESTS: u64 = 4096;
+
+/// State tracked locally for a single client key
+struct Bucket {
+    /// Currently available tokens
+    tokens: f64,
+    /// Instant the bucket was last refilled
+    last_refill: Instant,
+    /// Tokens consumed locally since the last Redis reconciliation
+    pending: f64,
+    /// Local requests since the last Redis reconciliation
+    since_reconcile: u32,
+    /// Instant of the last Redis reconciliation
+    last_reconcile: Instant,
+}
+
+/// Token-bucket rate limiter keyed by client identity
+pub struct RateLimiter {
+    /// Sustained refill rate, in tokens per second
+    rps: f64,
+    /// Maximum bucket depth
+    burst: f64,
+    /// Header to read the client identity from, e.g. `X-Forwarded-For`
+    header: Option<String>,
+    /// Per-key local buckets
+    buckets: DashMap<String, Bucket>,
+    /// Idle duration after which a bucket is evicted during a sweep
+    max_idle: Duration,
+    /// Total requests seen, used to schedule periodic idle sweeps
+    seen: AtomicU64,
+    /// Optional authoritative shared counter
+    redis: Option<redis::Client>,
+    /// Multiplexed connection, dialed once and cloned per reconciliation so
+    /// a hot key does not open a fresh connection on every reconcile.
+    conn: tokio::sync::OnceCell<redis::aio::MultiplexedConnection>,
+}
+
+impl RateLimiter {
+    /// Construct a limiter from the parsed configuration
+    pub fn new(
+        rps: f64,
+        burst: f64,
+        header: Option<String>,
+        redis_url: Option<&str>,
+    ) -> Result<Self, redis::RedisError> {
+        let redis = match redis_url {
+            Some(url) => Some(redis::Client::open(url)?),
+            None => None,
+        };
+        // A bucket idle for longer than the time it takes to refill from
+        // empty to full carries no state worth keeping: re-creating it on
+        // the next request yields an identical full bucket. Evicting past
+        // that point is therefore lossless and bounds memory.
+        let max_idle = Duration::from_secs_f64((burst / rps).max(1.0));
+        Ok(RateLimiter {
+            rps,
+            burst,
+            header,
+            buckets: DashMap::new(),
+            max_idle,
+            seen: AtomicU64::new(0),
+            redis,
+            conn: tokio::sync::OnceCell::new(),
+        })
+    }
+
+    /// Resolve the client identity for a request
+    ///
+    /// Uses the configured forwarding header when present and non-empty,
+    /// otherwise falls back to the connecting peer's IP address.
+    pub fn client_key(&self, headers: &HeaderMap, peer: &SocketAddr) -> String {
+        if let Some(name) = &self.header {
+            if let Some(value) = headers.get(name) {
+                if let Ok(text) = value.to_str() {
+                    // A forwarding header may carry a list; the original
+                    // client is the first entry.
+                    let first = text.split(',').next().unwrap_or(text).trim();
+                    if !first.is_empty() {
+                        return first.to_owned();
+                    }
+                }
+            }
+        }
+        peer.ip().to_string()
+    }
+
+    /// Account for a request of `cost` points and report whether it is allowed
+    ///
+    /// Refills the local bucket, rejects when fewer than `cost` tokens
+    /// remain, and opportunistically reconciles against Redis.
+    pub async fn check(&self, key: &str, cost: f64) -> bool {
+        let now = Instant::now();
+        let (allowed, reconcile) = {
+            let mut bucket = self.buckets.entry(key.to_owned()).or_insert_with(
+                || Bucket {
+                    tokens: self.burst,
+                    last_refill: now,
+                    pending: 0.0,
+                    since_reconcile: 0,
+                    last_reconcile: now,
+                },
+            );
+
+            // Refill proportional to elapsed time, capped at the burst depth.
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens =
+                (bucket.tokens + elapsed.as_secs_f64() * self.rps).min(self.burst);
+            bucket.last_refill = now;
+
+            let allowed = bucket.tokens >= cost;
+            if allowed {
+                bucket.tokens -= cost;
+                bucket.pending += cost;
+            }
+            bucket.since_reconcile += 1;
+
+            let due = bucket.since_reconcile >= RECONCILE_EVERY_REQUESTS
+                || now.saturating_duration_since(bucket.last_reconcile)
+                    >= RECONCILE_EVERY;
+            let reconcile = if self.redis.is_some() && due {
+                let pending = std::mem::take(&mut bucket.pending);
+                bucket.since_reconcile = 0;
+                bucket.last_reconcile = now;
+                Some(pending)
+            } else {
+                None
+            };
+            (allowed, reconcile)
+        };
+
+        // Periodically drop buckets that have been idle long enough to have
+        // refilled completely. Done outside the entry guard above to avoid
+        // deadlocking on a shard lock we already hold.
+        if self.seen.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY_REQUESTS == 0 {
+            // Tokens an evicted bucket still owes to Redis are folded into the
+            // shared sum before it is dropped, so the sum doesn't undercount a
+            // long-lived-then-idle client. Without a backend `pending` is
+            // never reconciled, so ignore it there.
+            let mut owed = Vec::new();
+            self.buckets.retain(|k, b| {
+                let keep = now.saturating_duration_since(b.last_refill) < self.max_idle;
+                if !keep && self.redis.is_some() && b.pending > 0.0 {
+                    owed.push((k.clone(), b.pending));
+                }
+                keep
+            });
+            for (owed_key, pending) in owed {
+                self.reconcile(&owed_key, pending).await;
+            }
+        }
+
+        // Reconcile outside the DashMap guard so the Redis round-trip does
+        // not block other keys sharing the shard lock.
+        if let Some(pending) = reconcile {
+            self.reconcile(key, pending).await;
+        }
+        allowed
+    }
+
+    /// Fold the locally-consumed tokens into the authoritative Redis counter
+    async fn reconcile(&self, key: &str, pending: f64) {
+        if self.redis.is_none() {
+            return;
+        }
+        if let Err(e) = self.reconcile_inner(key, pending).await {
+            // A Redis outage must not take down request serving; the local
+            // bucket keeps enforcing an approximate limit in the meantime.
+            warn!("rate-limit reconciliation failed: {e}");
+        }
+    }
+
+    /// Multiplexed connection, dialed on first use and shared thereafter
+    ///
+    /// `MultiplexedConnection` drives many concurrent commands over a single
+    /// socket, so cloning the cached handle per reconcile avoids the
+    /// connection churn of re-dialing on every hot key.
+    async fn connection(
+        &self,
+        client: &redis::Client,
+    ) -> Result<redis::aio::MultiplexedConnection, redis::RedisError> {
+        self.conn
+            .get_or_try_init(|| client.get_multiplexed_async_connection())
+            .await
+            .cloned()
+    }
+
+    /// Perform the `INCR`/`EXPIRE` against Redis for a reconciliation window
+    async fn reconcile_inner(
+        &self,
+        key: &str,
+        pending: f64,
+    ) -> Result<(), redis::RedisError> {
+        let client = self.redis.as_ref().expect("reconcile requires Redis");
+        let mut conn = self.connection(client).await?;
+        // Window the counter over the refill period so the shared count
+        // ages out rather than growing without bound.
+        let window = (self.burst / self.rps).ceil().max(1.0) as u64;
+        let redis_key = format!("randsrv:rl:{key}");
+        let total: f64 = redis::pipe()
+            .atomic()
+            .cmd("INCRBYFLOAT")
+            .arg(&redis_key)
+            .arg(pending)
+            .cmd("EXPIRE")
+            .arg(&redis_key)
+            .arg(window)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        // If the windowed cross-instance total has run past the burst
+        // allowance, drain the local bucket so the next request is rejected.
+        // This only tightens the local limit opportunistically; it is not a
+        // hard global ceiling (see the module docs).
+        if total > self.burst {
+            if let Some(mut bucket) = self.buckets.get_mut(key) {
+                bucket.tokens = 0.0;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Unit tests for the randomness service
+
+use super::*;
+
+use axum::http::StatusCode;
+
+use ratelimit::RateLimiter;
+
+#[test]
+fn error_status_codes() {
+    // Client mistakes map to 400.
+    assert_eq!(Error::BadPoint.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(Error::BadEpoch(5).status_code(), StatusCode::BAD_REQUEST);
+    let decode_err = BASE64.decode("!!!").unwrap_err();
+    assert_eq!(
+        Error::Base64(decode_err).status_code(),
+        StatusCode::BAD_REQUEST
+    );
+    // Oversized payloads map to 413.
+    assert_eq!(
+        Error::TooManyPoints.status_code(),
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+    // Rate limiting maps to 429.
+    assert_eq!(
+        Error::RateLimited.status_code(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    // Server-side faults map to 500.
+    assert_eq!(
+        Error::LockFailure.status_code(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[tokio::test]
+async fn token_bucket_charges_points_and_rejects_when_empty() {
+    // 1 token/sec sustained, depth of 5. Immediate calls see negligible
+    // refill, so the bucket behaves as a fixed pool of `burst` tokens.
+    let limiter = RateLimiter::new(1.0, 5.0, None, None).unwrap();
+    // A batch is charged one token per point.
+    assert!(limiter.check("client-a", 5.0).await);
+    // Bucket is now empty; the next point is rejected.
+    assert!(!limiter.check("client-a", 1.0).await);
+    // A different client has its own independent bucket.
+    assert!(limiter.check("client-b", 3.0).await);
+}
+
+#[tokio::test]
+async fn oversized_batch_is_rejected_outright() {
+    let limiter = RateLimiter::new(1.0, 5.0, None, None).unwrap();
+    // A single batch larger than the burst depth can never be served.
+    assert!(!limiter.check("client", 6.0).await);
+}
+
+/// Build a `Config` from command-line-style arguments for testing.
+fn test_config(args: &[&str]) -> Config {
+    use clap::Parser;
+    let mut full = vec!["star-randsrv"];
+    full.extend_from_slice(args);
+    Config::parse_from(full)
+}
+
+#[test]
+fn anchored_epoch_is_a_pure_function_of_the_clock() {
+    let config = test_config(&[
+        "--epoch-seconds",
+        "10",
+        "--epoch-base-time",
+        "1000",
+        "--first-epoch",
+        "0",
+        "--last-epoch",
+        "3",
+    ]);
+    // At the reference time we are at the first epoch.
+    assert_eq!(anchored_epoch(&config, 1000), 0);
+    // Two and a bit periods in.
+    assert_eq!(anchored_epoch(&config, 1025), 2);
+    // The cycle wraps back to the first epoch after `range` periods.
+    assert_eq!(anchored_epoch(&config, 1040), 0);
+    // Times before the reference floor correctly: an exact multiple and a
+    // sub-period offset both land in the period immediately before the anchor.
+    assert_eq!(anchored_epoch(&config, 990), 3);
+    assert_eq!(anchored_epoch(&config, 993), 3);
+}
+
+#[test]
+fn anchored_epoch_respects_a_nonzero_first_epoch() {
+    let config = test_config(&[
+        "--epoch-seconds",
+        "5",
+        "--epoch-base-time",
+        "0",
+        "--first-epoch",
+        "10",
+        "--last-epoch",
+        "13",
+    ]);
+    assert_eq!(anchored_epoch(&config, 0), 10);
+    assert_eq!(anchored_epoch(&config, 7), 11);
+    assert_eq!(anchored_epoch(&config, 20), 10);
+}
+
+#[test]
+fn persisted_state_round_trips_and_is_owner_only() {
+    use persist::PersistedState;
+
+    let path = std::env::temp_dir().join("star-randsrv-persist-test.bin");
+    let _ = std::fs::remove_file(&path);
+
+    // A missing file reads back as `None`.
+    assert!(persist::load(&path).unwrap().is_none());
+
+    let state = PersistedState {
+        server: vec![0xde, 0xad, 0xbe, 0xef],
+        epoch: 42,
+    };
+    persist::save(&path, &state).unwrap();
+
+    let loaded = persist::load(&path).unwrap().expect("file should exist");
+    assert_eq!(loaded.server, vec![0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(loaded.epoch, 42);
+
+    // The key material must not be world- or group-readable.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let _ = std::fs::remove_file(&path);
+}